@@ -4,6 +4,7 @@ use std::fs::File;
 use std::io::{Read, Write};
 use ron::ser::to_string;
 use ron::de::from_str;
+use chrono::{Days, Local, Months, NaiveDate};
 
 const FILE_NAME: &str = "tasks.txt";
 
@@ -12,6 +13,354 @@ struct Task {
     id: usize,             // Унікальний ідентифікатор завдання
     description: String,   // Опис завдання
     completed: bool,       // Стан виконання
+    #[serde(default)]
+    priority: u8,          // Пріоритет 1..5 (0 — не задано)
+    #[serde(default)]
+    tags: Vec<String>,     // Теги/проєкти, розпізнані з опису
+    #[serde(default)]
+    due: Option<NaiveDate>, // Термін виконання
+    #[serde(default)]
+    recurrence: Option<Recurrence>, // Правило повторення
+    #[serde(default)]
+    parent: Option<usize>, // ID батьківського завдання (для підзавдань)
+}
+
+/// Порядок сортування списку завдань
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+enum SortOrder {
+    #[default]
+    Urgency,
+    DueDate,
+    Priority,
+    Status,
+    Manual,
+}
+
+/// Одиниця інтервалу повторення
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+enum Unit {
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+/// Правило повторення у стилі `rec:2w` — кожні `every` одиниць `unit`
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, PartialEq)]
+struct Recurrence {
+    every: u32,
+    unit: Unit,
+}
+
+/// Намір щодо правила повторення при збереженні редагування:
+/// порожнє поле прибирає його, валідний запис встановлює,
+/// а невалідний непорожній — лишає чинне правило недоторканим
+enum RecurrenceEdit {
+    Keep,
+    Clear,
+    Set(Recurrence),
+}
+
+impl RecurrenceEdit {
+    /// Інтерпретує вміст поля повторення з popup-а редагування
+    fn from_input(value: &str) -> RecurrenceEdit {
+        let value = value.trim();
+        if value.is_empty() {
+            RecurrenceEdit::Clear
+        } else if let Some(rec) = Recurrence::parse(value) {
+            RecurrenceEdit::Set(rec)
+        } else {
+            RecurrenceEdit::Keep
+        }
+    }
+}
+
+impl Recurrence {
+    /// Розбирає компактний запис `1d`/`2w`/`1m`/`1y`
+    fn parse(value: &str) -> Option<Recurrence> {
+        let last = value.chars().next_back()?;
+        let unit = match last {
+            'd' => Unit::Day,
+            'w' => Unit::Week,
+            'm' => Unit::Month,
+            'y' => Unit::Year,
+            _ => return None,
+        };
+        let count = &value[..value.len() - last.len_utf8()];
+        let every: u32 = count.parse().ok()?;
+        if every == 0 {
+            return None;
+        }
+        Some(Recurrence { every, unit })
+    }
+
+    /// Зсуває дату на один інтервал уперед; місяці/роки обрізають
+    /// кінець місяця (напр. 31 січня + 1m → 28/29 лютого)
+    fn advance(&self, from: NaiveDate) -> NaiveDate {
+        // Завершення завдання не має «вибухати» на величезному інтервалі:
+        // обрізаємо множники й лишаємо дату без змін при виході за межі
+        let shifted = match self.unit {
+            Unit::Day => from.checked_add_days(Days::new(self.every as u64)),
+            Unit::Week => from.checked_add_days(Days::new(self.every as u64 * 7)),
+            Unit::Month => from.checked_add_months(Months::new(self.every)),
+            Unit::Year => from.checked_add_months(Months::new(self.every.saturating_mul(12))),
+        };
+        shifted.unwrap_or(from)
+    }
+
+    /// Повертає компактний запис для серіалізації/інтерфейсу
+    fn to_token(&self) -> String {
+        let unit = match self.unit {
+            Unit::Day => 'd',
+            Unit::Week => 'w',
+            Unit::Month => 'm',
+            Unit::Year => 'y',
+        };
+        format!("{}{unit}", self.every)
+    }
+}
+
+/// Результат розбору «магічного» вводу завдання
+#[derive(Debug, Default, PartialEq)]
+struct QuickAdd {
+    description: String,
+    priority: u8,
+    tags: Vec<String>,
+    due: Option<NaiveDate>,
+    recurrence: Option<Recurrence>,
+}
+
+/// Розбирає рядок на опис і спеціальні токени у стилі Vikunja quick-add magic:
+/// `!1`..`!5` — пріоритет, `#слово` — тег, `due:ДАТА` — термін
+/// (`due:2024-06-30`, `due:today`, `due:tomorrow`). Нерозпізнані `#`/`!`
+/// фрагменти лишаються в описі без змін.
+fn parse_quick_add(input: &str) -> QuickAdd {
+    let today = Local::now().date_naive();
+    let mut result = QuickAdd::default();
+    let mut kept: Vec<&str> = Vec::new();
+
+    for token in input.split_whitespace() {
+        if let Some(rest) = token.strip_prefix("due:") {
+            if let Some(date) = parse_due(rest, today) {
+                result.due = Some(date);
+                continue;
+            }
+        } else if let Some(rest) = token.strip_prefix("rec:") {
+            if let Some(rec) = Recurrence::parse(rest) {
+                result.recurrence = Some(rec);
+                continue;
+            }
+        } else if let Some(level) = token.strip_prefix('!') {
+            if level.len() == 1 {
+                if let Some(p @ 1..=5) = level.parse::<u8>().ok() {
+                    result.priority = p;
+                    continue;
+                }
+            }
+        } else if let Some(tag) = token.strip_prefix('#') {
+            if !tag.is_empty() && tag.chars().all(|c| c.is_alphanumeric() || c == '_' || c == '-') {
+                result.tags.push(tag.to_string());
+                continue;
+            }
+        }
+        kept.push(token);
+    }
+
+    result.description = kept.join(" ");
+    result
+}
+
+/// Перетворює значення `due:` на дату: ISO-формат або ключові слова
+fn parse_due(value: &str, today: NaiveDate) -> Option<NaiveDate> {
+    match value {
+        "today" => Some(today),
+        "tomorrow" => today.succ_opt(),
+        other => NaiveDate::parse_from_str(other, "%Y-%m-%d").ok(),
+    }
+}
+
+/// Оператор порівняння для числових/датових полів фільтра
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Lt,
+    Le,
+    Eq,
+    Ge,
+    Gt,
+}
+
+impl Comparison {
+    /// Застосовує оператор до результату `Ord::cmp`
+    fn holds(&self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            Comparison::Lt => ordering == Less,
+            Comparison::Le => ordering != Greater,
+            Comparison::Eq => ordering == Equal,
+            Comparison::Ge => ordering != Less,
+            Comparison::Gt => ordering == Greater,
+        }
+    }
+}
+
+/// AST мови пошуку/фільтрації над завданнями
+#[derive(Debug, PartialEq)]
+enum Filter {
+    Completed(bool),
+    Priority(Comparison, u8),
+    Due(Comparison, Option<NaiveDate>),
+    Tag(String),
+    Text(String),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    /// Розбирає рядок запиту у AST; повертає опис помилки при невдачі
+    fn parse(query: &str) -> Result<Filter, String> {
+        let spaced = query.replace('(', " ( ").replace(')', " ) ");
+        let tokens: Vec<&str> = spaced.split_whitespace().collect();
+        if tokens.is_empty() {
+            return Err("порожній запит".to_string());
+        }
+        let mut pos = 0;
+        let filter = parse_or(&tokens, &mut pos)?;
+        if pos != tokens.len() {
+            return Err(format!("зайвий токен: {}", tokens[pos]));
+        }
+        Ok(filter)
+    }
+
+    /// Перевіряє, чи завдання відповідає фільтру
+    fn matches(&self, task: &Task) -> bool {
+        match self {
+            Filter::Completed(state) => task.completed == *state,
+            Filter::Priority(op, value) => op.holds(task.priority.cmp(value)),
+            Filter::Due(op, value) => match (task.due, value) {
+                (Some(a), Some(b)) => op.holds(a.cmp(b)),
+                (None, None) => *op == Comparison::Eq,
+                _ => false,
+            },
+            Filter::Tag(tag) => task.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)),
+            Filter::Text(text) => task
+                .description
+                .to_lowercase()
+                .contains(&text.to_lowercase()),
+            Filter::And(a, b) => a.matches(task) && b.matches(task),
+            Filter::Or(a, b) => a.matches(task) || b.matches(task),
+            Filter::Not(inner) => !inner.matches(task),
+        }
+    }
+}
+
+fn parse_or(tokens: &[&str], pos: &mut usize) -> Result<Filter, String> {
+    let mut left = parse_and(tokens, pos)?;
+    while tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("or")) {
+        *pos += 1;
+        let right = parse_and(tokens, pos)?;
+        left = Filter::Or(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_and(tokens: &[&str], pos: &mut usize) -> Result<Filter, String> {
+    let mut left = parse_not(tokens, pos)?;
+    loop {
+        match tokens.get(*pos) {
+            // Кінець виразу або межа групи/альтернативи
+            None => break,
+            Some(t) if t.eq_ignore_ascii_case("or") || *t == ")" => break,
+            Some(t) if t.eq_ignore_ascii_case("and") => *pos += 1, // явний `and`
+            _ => {}                                                // неявний `and`
+        }
+        let right = parse_not(tokens, pos)?;
+        left = Filter::And(Box::new(left), Box::new(right));
+    }
+    Ok(left)
+}
+
+fn parse_not(tokens: &[&str], pos: &mut usize) -> Result<Filter, String> {
+    if tokens.get(*pos).is_some_and(|t| t.eq_ignore_ascii_case("not")) {
+        *pos += 1;
+        let inner = parse_not(tokens, pos)?;
+        return Ok(Filter::Not(Box::new(inner)));
+    }
+    parse_primary(tokens, pos)
+}
+
+fn parse_primary(tokens: &[&str], pos: &mut usize) -> Result<Filter, String> {
+    let token = *tokens.get(*pos).ok_or("несподіваний кінець запиту")?;
+    if token == "(" {
+        *pos += 1;
+        let inner = parse_or(tokens, pos)?;
+        if tokens.get(*pos) != Some(&")") {
+            return Err("очікувалась `)`".to_string());
+        }
+        *pos += 1;
+        return Ok(inner);
+    }
+    if token == ")" {
+        return Err("несподівана `)`".to_string());
+    }
+    *pos += 1;
+    parse_term(token)
+}
+
+/// Розбирає окремий терм-токен у листок AST
+fn parse_term(token: &str) -> Result<Filter, String> {
+    if let Some(tag) = token.strip_prefix('#') {
+        if tag.is_empty() {
+            return Err("порожній тег".to_string());
+        }
+        return Ok(Filter::Tag(tag.to_string()));
+    }
+    if let Some((field, value)) = token.split_once(':') {
+        match field {
+            "done" | "completed" => {
+                let state = value
+                    .parse::<bool>()
+                    .map_err(|_| format!("очікувалось true/false, маємо `{value}`"))?;
+                return Ok(Filter::Completed(state));
+            }
+            "priority" => {
+                let (op, rest) = split_comparison(value);
+                let level = rest
+                    .parse::<u8>()
+                    .map_err(|_| format!("некоректний пріоритет `{rest}`"))?;
+                return Ok(Filter::Priority(op, level));
+            }
+            "due" => {
+                let (op, rest) = split_comparison(value);
+                if rest == "none" {
+                    return Ok(Filter::Due(Comparison::Eq, None));
+                }
+                let date = NaiveDate::parse_from_str(rest, "%Y-%m-%d")
+                    .map_err(|_| format!("некоректна дата `{rest}`"))?;
+                return Ok(Filter::Due(op, Some(date)));
+            }
+            _ => {}
+        }
+    }
+    // Усе інше — підрядковий пошук по опису
+    Ok(Filter::Text(token.to_string()))
+}
+
+/// Відокремлює префіксний оператор порівняння (`<`, `<=`, `>=`, `>`, `=`)
+fn split_comparison(value: &str) -> (Comparison, &str) {
+    if let Some(rest) = value.strip_prefix("<=") {
+        (Comparison::Le, rest)
+    } else if let Some(rest) = value.strip_prefix(">=") {
+        (Comparison::Ge, rest)
+    } else if let Some(rest) = value.strip_prefix('<') {
+        (Comparison::Lt, rest)
+    } else if let Some(rest) = value.strip_prefix('>') {
+        (Comparison::Gt, rest)
+    } else if let Some(rest) = value.strip_prefix('=') {
+        (Comparison::Eq, rest)
+    } else {
+        (Comparison::Eq, value)
+    }
 }
 
 #[derive(Serialize, Deserialize, Default)]
@@ -21,32 +370,136 @@ struct TaskRepository {
 }
 
 impl TaskRepository {
-    /// Додає нове завдання
+    /// Додає нове завдання, розбираючи «магічні» токени з вводу
     fn add_task(&mut self, description: String) {
+        self.add_child(description, None);
+    }
+
+    /// Додає підзавдання з батьком `parent_id`
+    fn add_subtask(&mut self, parent_id: usize, description: String) {
+        self.add_child(description, Some(parent_id));
+    }
+
+    /// Спільна реалізація додавання завдання/підзавдання
+    fn add_child(&mut self, description: String, parent: Option<usize>) {
+        let parsed = parse_quick_add(&description);
         self.tasks.push(Task {
             id: self.next_id,
-            description,
+            description: parsed.description,
             completed: false,
+            priority: parsed.priority,
+            tags: parsed.tags,
+            due: parsed.due,
+            recurrence: parsed.recurrence,
+            parent,
         });
         self.next_id += 1;
     }
 
-    /// Редагує існуюче завдання за ID
-    fn edit_task(&mut self, id: usize, description: String) {
+    /// Повертає прогрес виконання підзавдань `(виконано, усього)`, якщо вони є
+    fn child_progress(&self, id: usize) -> Option<(usize, usize)> {
+        let total = self.tasks.iter().filter(|t| t.parent == Some(id)).count();
+        if total == 0 {
+            return None;
+        }
+        let done = self
+            .tasks
+            .iter()
+            .filter(|t| t.parent == Some(id) && t.completed)
+            .count();
+        Some((done, total))
+    }
+
+    /// Чи має завдання підзавдання
+    fn has_children(&self, id: usize) -> bool {
+        self.tasks.iter().any(|t| t.parent == Some(id))
+    }
+
+    /// Повертає завдання з упорядкуванням у межах кожної гілки та глибиною
+    fn hierarchical_view(&self, order: SortOrder) -> Vec<(&Task, usize)> {
+        let sorted = self.sorted_view(order);
+        let mut out = Vec::new();
+        push_branch(&sorted, None, 0, &mut out);
+        out
+    }
+
+    /// Редагує опис і правило повторення існуючого завдання за ID
+    fn edit_task(&mut self, id: usize, description: String, recurrence: RecurrenceEdit) {
         if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
             task.description = description;
+            match recurrence {
+                RecurrenceEdit::Keep => {}
+                RecurrenceEdit::Clear => task.recurrence = None,
+                RecurrenceEdit::Set(rec) => task.recurrence = Some(rec),
+            }
         }
     }
 
-    /// Видаляє завдання за ID
-    fn delete_task(&mut self, id: usize) {
-        self.tasks.retain(|task| task.id != id);
+    /// Видаляє завдання за ID. При `cascade` прибирає і всіх нащадків,
+    /// інакше переприв'язує дітей до батька видаленого завдання
+    fn delete_task(&mut self, id: usize, cascade: bool) {
+        if cascade {
+            let mut doomed = vec![id];
+            let mut queue = vec![id];
+            while let Some(current) = queue.pop() {
+                for child in self.tasks.iter().filter(|t| t.parent == Some(current)) {
+                    doomed.push(child.id);
+                    queue.push(child.id);
+                }
+            }
+            self.tasks.retain(|task| !doomed.contains(&task.id));
+        } else {
+            let grandparent = self
+                .tasks
+                .iter()
+                .find(|t| t.id == id)
+                .and_then(|t| t.parent);
+            for task in self.tasks.iter_mut().filter(|t| t.parent == Some(id)) {
+                task.parent = grandparent;
+            }
+            self.tasks.retain(|task| task.id != id);
+        }
     }
 
-    /// Позначає завдання як виконане
+    /// Позначає завдання як виконане; для повторюваних — породжує наступне,
+    /// а для підзавдань — автозавершує батька, коли всі діти виконані
     fn mark_completed(&mut self, id: usize) {
+        let mut next = None;
         if let Some(task) = self.tasks.iter_mut().find(|task| task.id == id) {
             task.completed = true;
+            if let Some(rec) = task.recurrence {
+                let base = task.due.unwrap_or_else(|| Local::now().date_naive());
+                let mut spawned = task.clone();
+                spawned.completed = false;
+                spawned.due = Some(rec.advance(base));
+                next = Some(spawned);
+            }
+        }
+        if let Some(mut spawned) = next {
+            spawned.id = self.next_id;
+            self.tasks.push(spawned);
+            self.next_id += 1;
+        }
+        self.autocomplete_parents(id);
+    }
+
+    /// Піднімається деревом і завершує батьків, усі діти яких виконані
+    fn autocomplete_parents(&mut self, child_id: usize) {
+        let mut current = self.tasks.iter().find(|t| t.id == child_id).and_then(|t| t.parent);
+        while let Some(pid) = current {
+            let children: Vec<bool> = self
+                .tasks
+                .iter()
+                .filter(|t| t.parent == Some(pid))
+                .map(|t| t.completed)
+                .collect();
+            if children.is_empty() || !children.iter().all(|&done| done) {
+                break;
+            }
+            if let Some(parent) = self.tasks.iter_mut().find(|t| t.id == pid) {
+                parent.completed = true;
+            }
+            current = self.tasks.iter().find(|t| t.id == pid).and_then(|t| t.parent);
         }
     }
 
@@ -71,6 +524,162 @@ impl TaskRepository {
         }
         TaskRepository::default()
     }
+
+    /// Повертає відсортований зріз завдань без зміни збереженого порядку
+    fn sorted_view(&self, order: SortOrder) -> Vec<&Task> {
+        let today = Local::now().date_naive();
+        let mut view: Vec<&Task> = self.tasks.iter().collect();
+        match order {
+            SortOrder::Manual => {}
+            SortOrder::Urgency => view.sort_by(|a, b| {
+                b.urgency(today)
+                    .partial_cmp(&a.urgency(today))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            }),
+            SortOrder::DueDate => view.sort_by_key(|task| task.due.unwrap_or(NaiveDate::MAX)),
+            SortOrder::Priority => {
+                // пріоритет 0 (не задано) — у кінець
+                view.sort_by_key(|task| if task.priority == 0 { u8::MAX } else { task.priority })
+            }
+            SortOrder::Status => view.sort_by_key(|task| task.completed),
+        }
+        view
+    }
+
+    /// Експортує завдання у стандартний формат todo.txt (по рядку на завдання)
+    fn export_todotxt(&self, path: &str) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        for task in &self.tasks {
+            file.write_all(task.to_todotxt().as_bytes())?;
+            file.write_all(b"\n")?;
+        }
+        Ok(())
+    }
+
+    /// Імпортує завдання з файлу todo.txt, замінюючи поточний список
+    /// і перенумеровуючи ID з чистої послідовності
+    fn import_todotxt(&mut self, path: &str) -> std::io::Result<()> {
+        let mut contents = String::new();
+        File::open(path)?.read_to_string(&mut contents)?;
+        self.tasks.clear();
+        self.next_id = 0;
+        for line in contents.lines() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let mut task = Task::from_todotxt(line);
+            task.id = self.next_id;
+            self.tasks.push(task);
+            self.next_id += 1;
+        }
+        Ok(())
+    }
+}
+
+/// Рекурсивно викладає гілку дерева завдань у плаский список із глибиною,
+/// зберігаючи вже задане упорядкування сусідів
+fn push_branch<'a>(
+    sorted: &[&'a Task],
+    parent: Option<usize>,
+    depth: usize,
+    out: &mut Vec<(&'a Task, usize)>,
+) {
+    for task in sorted.iter().filter(|t| t.parent == parent) {
+        out.push((*task, depth));
+        push_branch(sorted, Some(task.id), depth + 1, out);
+    }
+}
+
+impl Task {
+    /// Обчислює оцінку терміновості у стилі taskwarrior:
+    /// вага пріоритету + бонуси за прострочення/близький термін/теги
+    fn urgency(&self, today: NaiveDate) -> f32 {
+        if self.completed {
+            return 0.0;
+        }
+        let mut score = 0.0;
+        if (1..=5).contains(&self.priority) {
+            score += (6 - self.priority) as f32 * 2.0; // !1 — найвагоміший
+        }
+        if let Some(due) = self.due {
+            let days = (due - today).num_days();
+            if days < 0 {
+                score += 10.0; // прострочено
+            } else if days <= 3 {
+                score += 6.0 - days as f32; // скоро термін
+            }
+        }
+        score += self.tags.len() as f32;
+        score
+    }
+
+    /// Серіалізує завдання в один рядок todo.txt
+    fn to_todotxt(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if self.completed {
+            parts.push("x".to_string());
+        }
+        if (1..=5).contains(&self.priority) {
+            let letter = (b'A' + self.priority - 1) as char;
+            parts.push(format!("({letter})"));
+        }
+        parts.push(self.description.clone());
+        for tag in &self.tags {
+            parts.push(format!("+{tag}"));
+        }
+        if let Some(due) = self.due {
+            parts.push(format!("due:{}", due.format("%Y-%m-%d")));
+        }
+        if let Some(rec) = self.recurrence {
+            parts.push(format!("rec:{}", rec.to_token()));
+        }
+        parts.join(" ")
+    }
+
+    /// Розбирає один рядок todo.txt у завдання (ID призначає репозиторій)
+    fn from_todotxt(line: &str) -> Task {
+        let mut task = Task::default();
+        let mut words: Vec<&str> = Vec::new();
+
+        let mut tokens = line.split_whitespace().peekable();
+        if tokens.peek() == Some(&"x") {
+            task.completed = true;
+            tokens.next();
+        }
+        if let Some(first) = tokens.peek() {
+            if first.len() == 3 && first.starts_with('(') && first.ends_with(')') {
+                let letter = first.as_bytes()[1];
+                if letter.is_ascii_uppercase() {
+                    // todo.txt дозволяє (A)..(Z); обрізаємо до внутрішньої шкали 1..5
+                    task.priority = (letter - b'A' + 1).min(5);
+                    tokens.next();
+                }
+            }
+        }
+
+        for token in tokens {
+            if let Some(tag) = token.strip_prefix('+').or_else(|| token.strip_prefix('@')) {
+                if !tag.is_empty() {
+                    task.tags.push(tag.to_string());
+                    continue;
+                }
+            } else if let Some(value) = token.strip_prefix("due:") {
+                if let Ok(date) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+                    task.due = Some(date);
+                    continue;
+                }
+            } else if let Some(value) = token.strip_prefix("rec:") {
+                if let Some(rec) = Recurrence::parse(value) {
+                    task.recurrence = Some(rec);
+                    continue;
+                }
+            }
+            words.push(token);
+        }
+
+        task.description = words.join(" ");
+        task
+    }
 }
 
 /// Основна функція
@@ -88,8 +697,15 @@ struct ToDoApp {
     manager: TaskRepository,   // Репозиторій із завданнями
     new_description: String,   // Поле для вводу нового завдання
     edit_description: String,  // Поле для редагування завдання
+    edit_recurrence: String,   // Поле для правила повторення (напр. `1w`)
+    edit_recurrence_error: bool, // Невалідний ввід повторення під час редагування
     show_edit_popup: bool,     // Показувати чи приховувати вікно редагування
     edit_id_task: Option<usize>, // ID завдання, яке редагується
+    search_query: String,      // Вираз пошуку/фільтрації
+    sort_order: SortOrder,     // Обраний порядок сортування
+    subtask_description: String, // Поле для вводу підзавдання
+    subtask_parent: Option<usize>, // Батько для нового підзавдання
+    delete_confirm: Option<usize>, // Завдання з дітьми, яке очікує підтвердження
 }
 
 impl ToDoApp {
@@ -99,8 +715,15 @@ impl ToDoApp {
             manager: TaskRepository::load_from_file(),
             new_description: String::new(),
             edit_description: String::new(),
+            edit_recurrence: String::new(),
+            edit_recurrence_error: false,
             show_edit_popup: false,
             edit_id_task: None,
+            search_query: String::new(),
+            sort_order: SortOrder::default(),
+            subtask_description: String::new(),
+            subtask_parent: None,
+            delete_confirm: None,
         }
     }
 }
@@ -125,29 +748,133 @@ impl eframe::App for ToDoApp {
                     }
                 });
 
+                ui.horizontal(|ui| {
+                    if ui.button("📤 Експорт").clicked() {
+                        let _ = self.manager.export_todotxt("todo.txt");
+                    }
+                    if ui.button("📥 Імпорт").clicked() && self.manager.import_todotxt("todo.txt").is_ok() {
+                        self.manager.save_to_file();
+                    }
+                });
+
             });
 
             ui.separator();
 
-            // Відображення існуючих завдань
-            if self.manager.tasks.is_empty() {
+            // Рядок пошуку/фільтрації
+            ui.horizontal(|ui| {
+                ui.label("🔍");
+                ui.text_edit_singleline(&mut self.search_query);
+            });
+
+            // Вибір порядку сортування/групування
+            ui.horizontal(|ui| {
+                ui.label("↕ Сортування:");
+                egui::ComboBox::from_id_source("sort_order")
+                    .selected_text(match self.sort_order {
+                        SortOrder::Urgency => "за терміновістю",
+                        SortOrder::DueDate => "за датою",
+                        SortOrder::Priority => "за пріоритетом",
+                        SortOrder::Status => "за станом",
+                        SortOrder::Manual => "вручну",
+                    })
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut self.sort_order, SortOrder::Urgency, "за терміновістю");
+                        ui.selectable_value(&mut self.sort_order, SortOrder::DueDate, "за датою");
+                        ui.selectable_value(&mut self.sort_order, SortOrder::Priority, "за пріоритетом");
+                        ui.selectable_value(&mut self.sort_order, SortOrder::Status, "за станом");
+                        ui.selectable_value(&mut self.sort_order, SortOrder::Manual, "вручну");
+                    });
+            });
+
+            // Порожній запит показує все; помилковий — все, але з попередженням
+            let filter = if self.search_query.trim().is_empty() {
+                None
+            } else {
+                match Filter::parse(&self.search_query) {
+                    Ok(filter) => Some(filter),
+                    Err(err) => {
+                        ui.colored_label(egui::Color32::RED, format!("⚠ {err}"));
+                        None
+                    }
+                }
+            };
+
+            // Відображення існуючих завдань у обраному порядку, з вкладеністю
+            let today = Local::now().date_naive();
+            let tasks: Vec<(Task, usize)> = self
+                .manager
+                .hierarchical_view(self.sort_order)
+                .into_iter()
+                .map(|(task, depth)| (task.clone(), depth))
+                .collect();
+            if tasks.is_empty() {
                 ui.label("✨ На сьогодні нічого не заплановано. Гуляй сміло!");
             } else {
-                for task in self.manager.tasks.clone() {
+                for (task, depth) in tasks {
+                    // При активному фільтрі показуємо плаский список збігів (як у chunk0-4),
+                    // щоб відступ не «висів» під прихованим батьком
+                    let depth = if let Some(filter) = &filter {
+                        if !filter.matches(&task) {
+                            continue;
+                        }
+                        0
+                    } else {
+                        depth
+                    };
+                    let overdue = !task.completed
+                        && task.due.is_some_and(|due| due < today);
+                    let progress = self.manager.child_progress(task.id);
                     ui.group(|ui| {
                         ui.horizontal(|ui| {
-                            // Стиль для виконаних завдань
+                            // Відступ відповідно до глибини в дереві
+                            ui.add_space(depth as f32 * 20.0);
+
+                            // Стиль для виконаних і прострочених завдань
                             if task.completed {
                                 ui.label(egui::RichText::new(&task.description).strikethrough());
+                            } else if overdue {
+                                ui.label(
+                                    egui::RichText::new(&task.description).color(egui::Color32::RED),
+                                );
                             } else {
                                 ui.label(&task.description);
                             }
 
+                            // Значки розпізнаних пріоритету/дати/тегів
+                            if task.priority > 0 {
+                                ui.label(egui::RichText::new(format!("!{}", task.priority)).strong());
+                            }
+                            if let Some(due) = task.due {
+                                ui.label(egui::RichText::new(format!("🗓 {}", due)).weak());
+                            }
+                            for tag in &task.tags {
+                                ui.label(egui::RichText::new(format!("#{tag}")).weak());
+                            }
+                            if let Some(rec) = task.recurrence {
+                                ui.label(egui::RichText::new(format!("🔁 {}", rec.to_token())).weak());
+                            }
+                            // Прогрес виконання підзавдань
+                            if let Some((done, total)) = progress {
+                                ui.label(egui::RichText::new(format!("{done}/{total} done")).weak());
+                            }
+
                             // Кнопка редагування
                             if ui.button("✏ Редагувати").clicked() {
                                 self.show_edit_popup = true;
                                 self.edit_id_task = Some(task.id);
                                 self.edit_description = task.description.clone();
+                                self.edit_recurrence = task
+                                    .recurrence
+                                    .map(|rec| rec.to_token())
+                                    .unwrap_or_default();
+                                self.edit_recurrence_error = false;
+                            }
+
+                            // Кнопка додавання підзавдання
+                            if ui.button("➕ Підзавдання").clicked() {
+                                self.subtask_parent = Some(task.id);
+                                self.subtask_description.clear();
                             }
 
                             // Кнопка завершення
@@ -158,16 +885,66 @@ impl eframe::App for ToDoApp {
                                 }
                             }
 
-                            // Кнопка видалення
+                            // Кнопка видалення: завдання з дітьми потребує підтвердження
                             if ui.button("❌ Вилучити").clicked() {
-                                self.manager.delete_task(task.id);
-                                self.manager.save_to_file();
+                                if self.manager.has_children(task.id) {
+                                    self.delete_confirm = Some(task.id);
+                                } else {
+                                    self.manager.delete_task(task.id, false);
+                                    self.manager.save_to_file();
+                                }
                             }
                         });
                     });
                 }
             }
 
+            // Вікно для додавання підзавдання
+            if let Some(parent_id) = self.subtask_parent {
+                egui::Window::new("➕ Нове підзавдання")
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.text_edit_singleline(&mut self.subtask_description);
+                        ui.horizontal(|ui| {
+                            if ui.button("💾 Додати").clicked() && !self.subtask_description.is_empty() {
+                                self.manager
+                                    .add_subtask(parent_id, self.subtask_description.clone());
+                                self.manager.save_to_file();
+                                self.subtask_parent = None;
+                            }
+                            if ui.button("❌ Відмінити").clicked() {
+                                self.subtask_parent = None;
+                            }
+                        });
+                    });
+            }
+
+            // Вікно підтвердження видалення завдання з підзавданнями
+            if let Some(parent_id) = self.delete_confirm {
+                egui::Window::new("⚠ Видалити з підзавданнями?")
+                    .collapsible(false)
+                    .resizable(false)
+                    .show(ctx, |ui| {
+                        ui.label("Це завдання має підзавдання. Що зробити?");
+                        ui.horizontal(|ui| {
+                            if ui.button("🗑 Видалити все").clicked() {
+                                self.manager.delete_task(parent_id, true);
+                                self.manager.save_to_file();
+                                self.delete_confirm = None;
+                            }
+                            if ui.button("↑ Лише це, підняти дітей").clicked() {
+                                self.manager.delete_task(parent_id, false);
+                                self.manager.save_to_file();
+                                self.delete_confirm = None;
+                            }
+                            if ui.button("❌ Відмінити").clicked() {
+                                self.delete_confirm = None;
+                            }
+                        });
+                    });
+            }
+
             // Вікно для редагування завдання
             if self.show_edit_popup {
                 egui::Window::new("✏ Редагувати завдання")
@@ -176,14 +953,32 @@ impl eframe::App for ToDoApp {
                     .show(ctx, |ui| {
                         ui.label("Зміни завдання:");
                         ui.text_edit_singleline(&mut self.edit_description);
+                        ui.label("Повторення (напр. 1d/2w/1m/1y, порожнє — вимкнути):");
+                        ui.text_edit_singleline(&mut self.edit_recurrence);
+                        if self.edit_recurrence_error {
+                            ui.colored_label(
+                                egui::Color32::RED,
+                                "⚠ Нерозпізнане повторення — чинне правило збережено",
+                            );
+                        }
 
                         ui.horizontal(|ui| {
                             if ui.button("💾 Зберегти").clicked() {
+                                let edit = RecurrenceEdit::from_input(&self.edit_recurrence);
+                                // Непорожній, але невалідний ввід: лишаємось у вікні й підсвічуємо
+                                self.edit_recurrence_error = matches!(edit, RecurrenceEdit::Keep)
+                                    && !self.edit_recurrence.trim().is_empty();
                                 if let Some(task_id) = self.edit_id_task {
-                                    self.manager.edit_task(task_id, self.edit_description.clone());
+                                    self.manager.edit_task(
+                                        task_id,
+                                        self.edit_description.clone(),
+                                        edit,
+                                    );
                                     self.manager.save_to_file();
                                 }
-                                self.show_edit_popup = false;
+                                if !self.edit_recurrence_error {
+                                    self.show_edit_popup = false;
+                                }
                             }
 
                             if ui.button("❌ Відмінити").clicked() {